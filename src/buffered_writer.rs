@@ -0,0 +1,305 @@
+use std::sync::{
+    atomic::{AtomicU64, Ordering},
+    Arc,
+};
+
+use tokio::sync::mpsc;
+use tokio::time::{Duration, Instant};
+
+use crate::{Bucket, Measurement};
+
+/// Default number of buffered measurements before a flush is forced,
+/// mirroring the `INFLUX_WRITER_MAX_BUFFER` default of the influx-writer crate.
+const DEFAULT_MAX_BUFFER: usize = 4096;
+
+/// Default interval between time-based flushes.
+const DEFAULT_FLUSH_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Configuration for a [`BufferedWriter`].
+pub struct BufferedWriterConfig {
+    max_buffer: usize,
+    flush_interval: Duration,
+    write_timeout: Duration,
+    channel_capacity: usize,
+}
+
+impl BufferedWriterConfig {
+    /// Maximum number of buffered measurements before a flush is forced
+    /// regardless of the flush interval. Defaults to 4096.
+    pub fn max_buffer(mut self, max_buffer: usize) -> Self {
+        self.max_buffer = max_buffer;
+        self
+    }
+
+    /// How often the background task flushes on a timer, independent of
+    /// buffer size. Defaults to 1 second.
+    pub fn flush_interval(mut self, flush_interval: Duration) -> Self {
+        self.flush_interval = flush_interval;
+        self
+    }
+
+    /// Per-flush HTTP write timeout, forwarded to [`Bucket::write`].
+    pub fn write_timeout(mut self, write_timeout: Duration) -> Self {
+        self.write_timeout = write_timeout;
+        self
+    }
+
+    /// Capacity of the `send`/`try_send` channel used to hand measurements
+    /// to the background task. Defaults to 4096, independent of any
+    /// `max_buffer` set on this config.
+    pub fn channel_capacity(mut self, channel_capacity: usize) -> Self {
+        self.channel_capacity = channel_capacity;
+        self
+    }
+}
+
+impl Default for BufferedWriterConfig {
+    fn default() -> Self {
+        Self {
+            max_buffer: DEFAULT_MAX_BUFFER,
+            flush_interval: DEFAULT_FLUSH_INTERVAL,
+            write_timeout: Duration::from_secs(10),
+            channel_capacity: DEFAULT_MAX_BUFFER,
+        }
+    }
+}
+
+enum Command {
+    Write(Measurement),
+    Flush(tokio::sync::oneshot::Sender<()>),
+    Shutdown(tokio::sync::oneshot::Sender<Vec<Measurement>>),
+}
+
+/// A non-blocking handle to a background task that accumulates measurements
+/// written through [`Bucket`] and flushes them in batches, either once
+/// `max_buffer` measurements have queued up or once `flush_interval` has
+/// elapsed, whichever comes first.
+///
+/// Cloning a `BufferedWriter` is cheap; every clone shares the same
+/// background task and error counter.
+#[derive(Clone)]
+pub struct BufferedWriter {
+    sender: mpsc::Sender<Command>,
+    write_errors: Arc<AtomicU64>,
+}
+
+impl BufferedWriter {
+    pub(crate) fn spawn(bucket: Bucket, config: BufferedWriterConfig) -> Self {
+        let (sender, receiver) = mpsc::channel(config.channel_capacity);
+        let write_errors = Arc::new(AtomicU64::new(0));
+        tokio::spawn(run(bucket, config, receiver, Arc::clone(&write_errors)));
+        Self {
+            sender,
+            write_errors,
+        }
+    }
+
+    /// Queue a measurement for the background task to flush later. This
+    /// never blocks on the network; it only waits if the internal channel
+    /// is momentarily full.
+    pub async fn send(&self, measurement: Measurement) -> Result<(), Measurement> {
+        self.sender
+            .send(Command::Write(measurement))
+            .await
+            .map_err(|e| match e.0 {
+                Command::Write(m) => m,
+                _ => unreachable!(),
+            })
+    }
+
+    /// Queue a measurement without waiting for channel space; returns the
+    /// measurement back if the channel is full or the task has shut down.
+    pub fn try_send(&self, measurement: Measurement) -> Result<(), Measurement> {
+        self.sender
+            .try_send(Command::Write(measurement))
+            .map_err(|e| match e.into_inner() {
+                Command::Write(m) => m,
+                _ => unreachable!(),
+            })
+    }
+
+    /// Force an immediate flush of whatever is currently buffered and wait
+    /// for it to complete.
+    pub async fn flush(&self) {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        if self.sender.send(Command::Flush(tx)).await.is_ok() {
+            let _ = rx.await;
+        }
+    }
+
+    /// Flush any remaining buffered measurements and stop the background
+    /// task, waiting for it to finish draining. If the final flush fails,
+    /// the measurements that could not be sent are returned instead of
+    /// being silently discarded, so the caller can retry or log them.
+    pub async fn shutdown(self) -> Vec<Measurement> {
+        let (tx, rx) = tokio::sync::oneshot::channel();
+        if self.sender.send(Command::Shutdown(tx)).await.is_ok() {
+            rx.await.unwrap_or_default()
+        } else {
+            Vec::new()
+        }
+    }
+
+    /// Total number of flushes that have failed (after any retries) since
+    /// this writer was created. Failed flushes are not silently dropped:
+    /// their measurements stay in the buffer for the next flush, and only
+    /// the oldest are dropped once it holds more than `max_buffer`.
+    pub fn write_error_count(&self) -> u64 {
+        self.write_errors.load(Ordering::Relaxed)
+    }
+}
+
+async fn run(
+    bucket: Bucket,
+    config: BufferedWriterConfig,
+    mut receiver: mpsc::Receiver<Command>,
+    write_errors: Arc<AtomicU64>,
+) {
+    let mut buffer: Vec<Measurement> = Vec::with_capacity(config.max_buffer);
+    let mut deadline = Instant::now() + config.flush_interval;
+
+    loop {
+        tokio::select! {
+            _ = tokio::time::sleep_until(deadline) => {
+                flush_buffer(&bucket, &mut buffer, config.write_timeout, &write_errors).await;
+                deadline = Instant::now() + config.flush_interval;
+            }
+            command = receiver.recv() => {
+                match command {
+                    Some(Command::Write(measurement)) => {
+                        buffer.push(measurement);
+                        let dropped = enforce_capacity(&mut buffer, config.max_buffer);
+                        if dropped > 0 {
+                            tracing::warn!(
+                                "BufferedWriter buffer full ({} measurements), dropped {dropped} oldest",
+                                config.max_buffer,
+                            );
+                        }
+                        if buffer.len() >= config.max_buffer {
+                            flush_buffer(&bucket, &mut buffer, config.write_timeout, &write_errors).await;
+                            deadline = Instant::now() + config.flush_interval;
+                        }
+                    }
+                    Some(Command::Flush(done)) => {
+                        flush_buffer(&bucket, &mut buffer, config.write_timeout, &write_errors).await;
+                        deadline = Instant::now() + config.flush_interval;
+                        let _ = done.send(());
+                    }
+                    Some(Command::Shutdown(done)) => {
+                        flush_buffer(&bucket, &mut buffer, config.write_timeout, &write_errors).await;
+                        let _ = done.send(buffer);
+                        return;
+                    }
+                    None => {
+                        flush_buffer(&bucket, &mut buffer, config.write_timeout, &write_errors).await;
+                        return;
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Drops the oldest measurements in `buffer` down to `max_buffer`, keeping
+/// the newest ones, and returns how many were dropped.
+fn enforce_capacity(buffer: &mut Vec<Measurement>, max_buffer: usize) -> usize {
+    if buffer.len() <= max_buffer {
+        return 0;
+    }
+    let excess = buffer.len() - max_buffer;
+    buffer.drain(0..excess);
+    excess
+}
+
+async fn flush_buffer(
+    bucket: &Bucket,
+    buffer: &mut Vec<Measurement>,
+    write_timeout: Duration,
+    write_errors: &Arc<AtomicU64>,
+) {
+    if buffer.is_empty() {
+        return;
+    }
+    match bucket.write(buffer, write_timeout).await {
+        Ok(_) => buffer.clear(),
+        Err(e) => {
+            write_errors.fetch_add(1, Ordering::Relaxed);
+            tracing::error!("BufferedWriter flush failed, measurements stay buffered: {e:?}");
+        }
+    }
+}
+
+impl Bucket {
+    /// Build a [`BufferedWriter`] on top of this bucket: a background tokio
+    /// task that accepts measurements through a cheap, non-blocking
+    /// channel and flushes them in batches on its own schedule.
+    pub fn buffered_writer(self, config: BufferedWriterConfig) -> BufferedWriter {
+        BufferedWriter::spawn(self, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::*;
+    use crate::{Client, FieldValue};
+
+    fn test_measurement(i: u64) -> Measurement {
+        let mut fields: BTreeMap<String, FieldValue> = Default::default();
+        fields.insert("i".to_string(), FieldValue::UInteger(i));
+        Measurement::builder("m").fields(fields).build().unwrap()
+    }
+
+    // Nothing listens on this address, so writes through it fail fast with
+    // a connection-refused error, letting tests exercise the retention path
+    // without a real InfluxDB server.
+    fn unreachable_bucket() -> Bucket {
+        Client::new("http://127.0.0.1:1", "test-token")
+            .unwrap()
+            .make_bucket("org", "bucket")
+            .unwrap()
+    }
+
+    #[test]
+    fn enforce_capacity_drops_the_oldest_measurements_over_the_limit() {
+        let mut buffer: Vec<Measurement> = (0..5u64).map(test_measurement).collect();
+        let dropped = enforce_capacity(&mut buffer, 3);
+        assert_eq!(dropped, 2);
+        let remaining: Vec<String> = buffer.iter().map(|m| m.to_string()).collect();
+        assert!(remaining[0].contains("i=2u"));
+        assert!(remaining[1].contains("i=3u"));
+        assert!(remaining[2].contains("i=4u"));
+    }
+
+    #[test]
+    fn enforce_capacity_is_a_no_op_under_the_limit() {
+        let mut buffer: Vec<Measurement> = (0..2u64).map(test_measurement).collect();
+        let dropped = enforce_capacity(&mut buffer, 3);
+        assert_eq!(dropped, 0);
+        assert_eq!(buffer.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn flush_buffer_retains_measurements_on_failure() {
+        let bucket = unreachable_bucket();
+        let write_errors = Arc::new(AtomicU64::new(0));
+        let mut buffer = vec![test_measurement(1)];
+        flush_buffer(&bucket, &mut buffer, Duration::from_secs(1), &write_errors).await;
+        assert_eq!(buffer.len(), 1);
+        assert_eq!(write_errors.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn shutdown_returns_unflushed_measurements_when_the_final_flush_fails() {
+        let bucket = unreachable_bucket();
+        let config = BufferedWriterConfig::default().flush_interval(Duration::from_secs(3600));
+        let writer = BufferedWriter::spawn(bucket, config);
+        writer.send(test_measurement(1)).await.unwrap();
+
+        let leftover = writer.shutdown().await;
+
+        assert_eq!(leftover.len(), 1);
+        assert!(leftover[0].to_string().contains("i=1u"));
+    }
+}
@@ -0,0 +1,95 @@
+use crate::FieldValue;
+
+/// Coerces a numeric value of any integer width into the `FieldValue`
+/// integer variant that can hold it losslessly, so the
+/// [`measure!`](crate::measure) macro can accept `i8..i64`, `u8..u64` and
+/// `usize` without callers reaching for an `as i64`/`as u64` cast. Widths
+/// that fit in `i64` (`i8..i64`, `u8..u32`) become [`FieldValue::Integer`];
+/// `u64`/`usize`, which may exceed `i64::MAX`, become [`FieldValue::UInteger`]
+/// instead of silently wrapping into a negative number.
+pub trait AsIntegerField {
+    #[doc(hidden)]
+    fn into_integer_field(self) -> FieldValue;
+}
+
+/// Coerces a numeric value into an `f64` for use as a
+/// [`FieldValue::Float`](crate::FieldValue::Float), so the [`measure!`](crate::measure)
+/// macro can accept any float width without callers reaching for an `as f64` cast.
+pub trait AsF64 {
+    fn into_f64(self) -> f64;
+}
+
+macro_rules! impl_into_integer_field_via_i64 {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl AsIntegerField for $t {
+                fn into_integer_field(self) -> FieldValue {
+                    FieldValue::Integer(i64::from(self))
+                }
+            }
+        )*
+    };
+}
+
+impl_into_integer_field_via_i64!(i8, i16, i32, i64, u8, u16, u32);
+
+impl AsIntegerField for u64 {
+    fn into_integer_field(self) -> FieldValue {
+        FieldValue::UInteger(self)
+    }
+}
+
+impl AsIntegerField for usize {
+    fn into_integer_field(self) -> FieldValue {
+        // Lossless on both 32- and 64-bit targets: widening, never truncating.
+        FieldValue::UInteger(self as u64)
+    }
+}
+
+impl AsF64 for f32 {
+    fn into_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl AsF64 for f64 {
+    fn into_f64(self) -> f64 {
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_integer_widths_become_integer_field() {
+        assert!(matches!(5i8.into_integer_field(), FieldValue::Integer(5)));
+        assert!(matches!(5u32.into_integer_field(), FieldValue::Integer(5)));
+        assert!(matches!(
+            i64::MIN.into_integer_field(),
+            FieldValue::Integer(i64::MIN)
+        ));
+    }
+
+    #[test]
+    fn u64_and_usize_become_uinteger_field_even_above_i64_max() {
+        // The exact bug this trait exists to avoid: `u64::MAX as i64` would
+        // silently wrap to -1 instead of failing to compile or erroring.
+        assert!(matches!(
+            u64::MAX.into_integer_field(),
+            FieldValue::UInteger(u64::MAX)
+        ));
+        assert!(matches!(
+            (usize::MAX).into_integer_field(),
+            FieldValue::UInteger(u) if u as usize == usize::MAX
+        ));
+        assert!(matches!(1u64.into_integer_field(), FieldValue::UInteger(1)));
+    }
+
+    #[test]
+    fn float_widths_coerce_to_f64() {
+        assert_eq!(1.5f32.into_f64(), 1.5f64);
+        assert_eq!(2.5f64.into_f64(), 2.5f64);
+    }
+}
@@ -0,0 +1,60 @@
+use std::io::Write;
+
+use flate2::{write::GzEncoder, Compression as Flate2Compression};
+
+/// Request-body compression for [`Bucket::write`](crate::Bucket::write).
+/// Only gzip is supported today, matching what the InfluxDB v2 write API
+/// accepts via `Content-Encoding: gzip`. Requires the `gzip` feature.
+#[derive(Debug, Clone, Copy)]
+pub struct Compression {
+    level: u32,
+}
+
+impl Compression {
+    /// Gzip-compress write bodies at the given level (0-9, where 9 is the
+    /// most compression and the most CPU). Levels above 9 are clamped to 9
+    /// rather than panicking later inside `flate2` when a flush first tries
+    /// to compress with them.
+    pub fn gzip(level: u32) -> Self {
+        Self {
+            level: level.min(9),
+        }
+    }
+}
+
+impl Default for Compression {
+    /// `flate2`'s default compression level (6): a reasonable compromise
+    /// between CPU and bandwidth.
+    fn default() -> Self {
+        Self::gzip(6)
+    }
+}
+
+pub(crate) fn compress(body: &str, compression: Compression) -> crate::Result<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Flate2Compression::new(compression.level));
+    encoder.write_all(body.as_bytes())?;
+    Ok(encoder.finish()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use super::*;
+
+    #[test]
+    fn gzip_clamps_levels_above_nine() {
+        assert_eq!(Compression::gzip(15).level, 9);
+        assert_eq!(Compression::gzip(9).level, 9);
+        assert_eq!(Compression::gzip(0).level, 0);
+    }
+
+    #[test]
+    fn compress_round_trips_through_gzip() {
+        let compressed = compress("hello world", Compression::gzip(6)).unwrap();
+        let mut decoder = flate2::read::GzDecoder::new(&compressed[..]);
+        let mut decompressed = String::new();
+        decoder.read_to_string(&mut decompressed).unwrap();
+        assert_eq!(decompressed, "hello world");
+    }
+}
@@ -7,6 +7,18 @@ use std::{
 use reqwest::Url;
 use thiserror::Error;
 
+mod buffered_writer;
+pub mod coerce;
+#[cfg(feature = "gzip")]
+mod compression;
+mod macros;
+mod retry;
+
+pub use buffered_writer::{BufferedWriter, BufferedWriterConfig};
+#[cfg(feature = "gzip")]
+pub use compression::Compression;
+pub use retry::RetryPolicy;
+
 #[derive(Error, Debug)]
 pub enum Error {
     #[error("HTTP client error")]
@@ -23,6 +35,10 @@ pub enum Error {
 
     #[error("No measurement fields set (at least one is required")]
     AtLeastOneMeasurementFieldRequired,
+
+    #[cfg(feature = "gzip")]
+    #[error("gzip compression error")]
+    CompressionError(#[from] std::io::Error),
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
@@ -30,6 +46,50 @@ pub type Result<T> = std::result::Result<T, Error>;
 pub struct Client {
     authenticated_client: Arc<reqwest::Client>,
     write_endpoint_url: Url,
+    precision: Precision,
+    retry_policy: Option<RetryPolicy>,
+    #[cfg(feature = "gzip")]
+    compression: Option<Compression>,
+}
+
+/// The timestamp precision InfluxDB should interpret write-endpoint
+/// timestamps with. Set on a [`Client`] and carried by every [`Bucket`] made
+/// from it, it both drives the
+/// `precision=` query parameter sent with each write and how far
+/// [`Bucket::write`] truncates each measurement's timestamp before sending
+/// it, so the two can never drift out of sync with each other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Precision {
+    Seconds,
+    Milliseconds,
+    Microseconds,
+    Nanoseconds,
+}
+
+impl Precision {
+    fn query_value(&self) -> &'static str {
+        match self {
+            Precision::Seconds => "s",
+            Precision::Milliseconds => "ms",
+            Precision::Microseconds => "us",
+            Precision::Nanoseconds => "ns",
+        }
+    }
+
+    fn truncate(&self, unix_timestamp: Duration) -> u128 {
+        match self {
+            Precision::Seconds => unix_timestamp.as_secs() as u128,
+            Precision::Milliseconds => unix_timestamp.as_millis(),
+            Precision::Microseconds => unix_timestamp.as_micros(),
+            Precision::Nanoseconds => unix_timestamp.as_nanos(),
+        }
+    }
+}
+
+impl Default for Precision {
+    fn default() -> Self {
+        Precision::Nanoseconds
+    }
 }
 
 fn make_authenticated_client_builder(auth_token: &str) -> Result<reqwest::ClientBuilder> {
@@ -51,19 +111,52 @@ impl Client {
         let result = Self {
             authenticated_client: Arc::new(client_builder.build()?),
             write_endpoint_url: make_write_endpoint_url(url)?,
+            precision: Precision::default(),
+            retry_policy: None,
+            #[cfg(feature = "gzip")]
+            compression: None,
         };
         Ok(result)
     }
 
+    /// Set the timestamp precision sent to InfluxDB for buckets made from
+    /// this client. Defaults to [`Precision::Nanoseconds`].
+    pub fn with_precision(mut self, precision: Precision) -> Self {
+        self.precision = precision;
+        self
+    }
+
+    /// Retry writes from buckets made from this client on transient
+    /// failures, per the given [`RetryPolicy`]. Disabled by default, in
+    /// which case a failed write returns immediately as before.
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Gzip-compress write bodies from buckets made from this client,
+    /// sending `Content-Encoding: gzip`. Disabled by default. Requires the
+    /// `gzip` feature.
+    #[cfg(feature = "gzip")]
+    pub fn with_compression(mut self, compression: Compression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
     pub fn make_bucket(&self, org: &str, bucket: &str) -> Result<Bucket> {
         let mut write_endpoint_url = self.write_endpoint_url.clone();
         write_endpoint_url
             .query_pairs_mut()
             .append_pair("org", org)
-            .append_pair("bucket", bucket);
+            .append_pair("bucket", bucket)
+            .append_pair("precision", self.precision.query_value());
         let result = Bucket {
             authenticated_client: Arc::clone(&self.authenticated_client),
             write_endpoint_url,
+            precision: self.precision,
+            retry_policy: self.retry_policy.clone(),
+            #[cfg(feature = "gzip")]
+            compression: self.compression,
         };
         Ok(result)
     }
@@ -158,6 +251,16 @@ impl Measurement {
     pub fn builder<S: Into<String>>(name: S) -> MeasurementBuilder {
         MeasurementBuilder::new(name.into())
     }
+
+    /// Render this measurement as a line-protocol line, truncating its
+    /// timestamp to `precision`. The precision a measurement is written at
+    /// is a property of the [`Bucket`] it's sent through (it drives the
+    /// `precision=` query parameter InfluxDB parses the timestamp with), so
+    /// it lives here rather than on `Measurement` itself — a `Measurement`
+    /// doesn't otherwise know which bucket it'll end up in.
+    fn to_line_protocol(&self, precision: Precision) -> String {
+        line_protocol(self, precision)
+    }
 }
 
 fn escape_comma_equals_space(tag_key: &str) -> String {
@@ -173,49 +276,60 @@ fn escape_field_value(field_value: &str) -> String {
         .replace(r#"""#, r#"\""#)
 }
 
+fn line_protocol(measurement: &Measurement, precision: Precision) -> String {
+    let escaped_name = measurement.name.replace(",", "\\,").replace(" ", "\\ ");
+    let optional_tags: Vec<String> = measurement
+        .tags
+        .iter()
+        .map(|(tag_key, value)| {
+            format!(
+                "{}={}",
+                escape_comma_equals_space(tag_key),
+                escape_comma_equals_space(value)
+            )
+        })
+        .collect();
+    let fields: Vec<String> = measurement
+        .fields
+        .iter()
+        .map(|(field_key, value)| {
+            format!(
+                "{}={}",
+                escape_comma_equals_space(field_key),
+                value.to_string()
+            )
+        })
+        .collect();
+    let tags_str = if optional_tags.is_empty() {
+        "".to_string()
+    } else {
+        format!(",{}", optional_tags.join(","))
+    };
+    format!(
+        "{}{} {} {}",
+        escaped_name,
+        tags_str,
+        fields.join(","),
+        precision.truncate(measurement.unix_timestamp)
+    )
+}
+
 impl ToString for Measurement {
+    /// Renders at [`Precision::Nanoseconds`], the most granular precision
+    /// InfluxDB supports — use [`Bucket::write`] to render (and send) a
+    /// measurement at the precision its destination bucket actually uses.
     fn to_string(&self) -> String {
-        let escaped_name = self.name.replace(",", "\\,").replace(" ", "\\ ");
-        let optional_tags: Vec<String> = self
-            .tags
-            .iter()
-            .map(|(tag_key, value)| {
-                format!(
-                    "{}={}",
-                    escape_comma_equals_space(tag_key),
-                    escape_comma_equals_space(value)
-                )
-            })
-            .collect();
-        let fields: Vec<String> = self
-            .fields
-            .iter()
-            .map(|(field_key, value)| {
-                format!(
-                    "{}={}",
-                    escape_comma_equals_space(field_key),
-                    value.to_string()
-                )
-            })
-            .collect();
-        let tags_str = if optional_tags.is_empty() {
-            "".to_string()
-        } else {
-            format!(",{}", optional_tags.join(","))
-        };
-        format!(
-            "{}{} {} {}",
-            escaped_name,
-            tags_str,
-            fields.join(","),
-            self.unix_timestamp.as_nanos()
-        )
+        line_protocol(self, Precision::Nanoseconds)
     }
 }
 
 pub struct Bucket {
     authenticated_client: Arc<reqwest::Client>,
     write_endpoint_url: Url,
+    precision: Precision,
+    retry_policy: Option<RetryPolicy>,
+    #[cfg(feature = "gzip")]
+    compression: Option<Compression>,
 }
 
 impl Bucket {
@@ -225,22 +339,86 @@ impl Bucket {
         timeout: Duration,
     ) -> Result<reqwest::Response> {
         // https://docs.influxdata.com/influxdb/v2.0/reference/syntax/line-protocol/
-        let lines: Vec<String> = measurement.iter().map(|m| m.to_string()).collect();
+        let lines: Vec<String> = measurement
+            .iter()
+            .map(|m| m.to_line_protocol(self.precision))
+            .collect();
         let body = lines.join("\n");
+        match &self.retry_policy {
+            None => self.send_once(&body, timeout).await,
+            Some(policy) => self.send_with_retry(&body, timeout, policy).await,
+        }
+    }
+
+    fn request_builder(&self, body: &str, timeout: Duration) -> Result<reqwest::RequestBuilder> {
+        let builder = self
+            .authenticated_client
+            .post(self.write_endpoint_url.clone())
+            .timeout(timeout);
+        #[cfg(feature = "gzip")]
+        let builder = match self.compression {
+            Some(compression) => builder
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(compression::compress(body, compression)?),
+            None => builder.body(body.to_string()),
+        };
+        #[cfg(not(feature = "gzip"))]
+        let builder = builder.body(body.to_string());
+        Ok(builder)
+    }
+
+    async fn send_once(&self, body: &str, timeout: Duration) -> Result<reqwest::Response> {
         tracing::debug!(
             "Sending measurements {url}: {body}",
             url = self.write_endpoint_url,
             body = body,
         );
-        let resp = self
-            .authenticated_client
-            .post(self.write_endpoint_url.clone())
-            .body(body)
-            .timeout(timeout)
-            .send()
-            .await?;
+        let resp = self.request_builder(body, timeout)?.send().await?;
         resp.error_for_status().map_err(Into::into)
     }
+
+    async fn send_with_retry(
+        &self,
+        body: &str,
+        timeout: Duration,
+        policy: &RetryPolicy,
+    ) -> Result<reqwest::Response> {
+        let mut attempt = 0;
+        loop {
+            tracing::debug!(
+                "Sending measurements {url}: {body}",
+                url = self.write_endpoint_url,
+                body = body,
+            );
+            let sent = self.request_builder(body, timeout)?.send().await;
+
+            let (retryable, backoff, outcome) = match sent {
+                Ok(resp) if resp.status().is_success() => return Ok(resp),
+                Ok(resp) => {
+                    let status = resp.status();
+                    let retryable = retry::is_retryable_status(status);
+                    let backoff = retry::retry_after(&resp)
+                        .unwrap_or_else(|| policy.backoff_for_attempt(attempt + 1));
+                    (retryable, backoff, resp.error_for_status().map_err(Error::from))
+                }
+                Err(e) => {
+                    let retryable = retry::is_retryable_error(&e);
+                    let backoff = policy.backoff_for_attempt(attempt + 1);
+                    (retryable, backoff, Err(Error::from(e)))
+                }
+            };
+
+            attempt += 1;
+            if !retryable || attempt >= policy.max_attempts_value() {
+                return outcome;
+            }
+            tracing::warn!(
+                "Write failed ({outcome:?}), retrying in {backoff:?} (attempt {attempt}/{max})",
+                max = policy.max_attempts_value(),
+            );
+            tokio::time::sleep(backoff).await;
+        }
+    }
 }
 
 #[cfg(test)]
@@ -330,4 +508,47 @@ mod tests {
             r#"myMeasurement,tagKey=🍭 fieldKey="Launch 🚀" 0"#,
         );
     }
+
+    fn one_second_measurement() -> Measurement {
+        let mut fields: BTreeMap<String, FieldValue> = Default::default();
+        fields.insert("fieldKey".to_string(), FieldValue::UInteger(1));
+        Measurement::builder("myMeasurement")
+            .fields(fields)
+            .timestamp(SystemTime::UNIX_EPOCH + Duration::from_secs(1))
+            .unwrap()
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn to_line_protocol_truncates_to_the_given_precision() {
+        let measurement = one_second_measurement();
+        assert_eq!(
+            line_protocol(&measurement, Precision::Seconds),
+            r#"myMeasurement fieldKey=1u 1"#,
+        );
+        assert_eq!(
+            line_protocol(&measurement, Precision::Milliseconds),
+            r#"myMeasurement fieldKey=1u 1000"#,
+        );
+        assert_eq!(
+            line_protocol(&measurement, Precision::Microseconds),
+            r#"myMeasurement fieldKey=1u 1000000"#,
+        );
+        assert_eq!(
+            line_protocol(&measurement, Precision::Nanoseconds),
+            r#"myMeasurement fieldKey=1u 1000000000"#,
+        );
+    }
+
+    #[test]
+    fn to_string_always_renders_at_nanosecond_precision() {
+        // `ToString` has no bucket to take a precision from, so it always
+        // renders at the most granular precision InfluxDB supports.
+        let measurement = one_second_measurement();
+        assert_eq!(
+            measurement.to_string(),
+            line_protocol(&measurement, Precision::Nanoseconds),
+        );
+    }
 }
@@ -0,0 +1,97 @@
+/// Build a [`Measurement`](crate::Measurement) declaratively, without
+/// hand-rolling the tag/field `BTreeMap`s or spelling out [`FieldValue`](crate::FieldValue)
+/// variants.
+///
+/// ```ignore
+/// let m = measure!("cpu", tag host = "h1", int used = 5, float load = 1.2, bool up = true, str msg = status)?;
+/// ```
+///
+/// Each field is introduced with a type keyword:
+///
+/// - `tag <key> = <expr>` — a tag; `<expr>` must implement `ToString`.
+/// - `int <key> = <expr>` — an integer field; `<expr>` may be any of
+///   `i8..i64`, `u8..u64` or `usize` via [`AsIntegerField`](crate::coerce::AsIntegerField) —
+///   a non-numeric expression fails to compile, and a `u64`/`usize` value
+///   too large for `i64` becomes a [`FieldValue::UInteger`](crate::FieldValue::UInteger)
+///   rather than silently wrapping negative through an `as i64` cast.
+/// - `float <key> = <expr>` — a float field; `<expr>` may be `f32` or `f64`
+///   via [`AsF64`](crate::coerce::AsF64).
+/// - `bool <key> = <expr>` — a boolean field; `<expr>` must be a `bool`.
+/// - `str <key> = <expr>` — a string field; `<expr>` must implement `ToString`.
+///
+/// Expands to a call through [`Measurement::builder`](crate::Measurement::builder)
+/// and returns the same `Result<Measurement>` that [`MeasurementBuilder::build`](crate::MeasurementBuilder::build) does.
+#[macro_export]
+macro_rules! measure {
+    ($name:expr, $($rest:tt)*) => {{
+        let mut __measure_tags: std::collections::BTreeMap<String, String> = Default::default();
+        let mut __measure_fields: std::collections::BTreeMap<String, $crate::FieldValue> =
+            Default::default();
+        $crate::measure!(@item __measure_tags, __measure_fields, $($rest)*);
+        $crate::Measurement::builder($name)
+            .tags(__measure_tags)
+            .fields(__measure_fields)
+            .build()
+    }};
+
+    (@item $tags:ident, $fields:ident, tag $key:ident = $val:expr $(, $($rest:tt)*)?) => {
+        $tags.insert(stringify!($key).to_string(), ($val).to_string());
+        $crate::measure!(@item $tags, $fields, $($($rest)*)?);
+    };
+    (@item $tags:ident, $fields:ident, int $key:ident = $val:expr $(, $($rest:tt)*)?) => {
+        $fields.insert(
+            stringify!($key).to_string(),
+            $crate::coerce::AsIntegerField::into_integer_field($val),
+        );
+        $crate::measure!(@item $tags, $fields, $($($rest)*)?);
+    };
+    (@item $tags:ident, $fields:ident, float $key:ident = $val:expr $(, $($rest:tt)*)?) => {
+        $fields.insert(
+            stringify!($key).to_string(),
+            $crate::FieldValue::Float($crate::coerce::AsF64::into_f64($val)),
+        );
+        $crate::measure!(@item $tags, $fields, $($($rest)*)?);
+    };
+    (@item $tags:ident, $fields:ident, bool $key:ident = $val:expr $(, $($rest:tt)*)?) => {
+        $fields.insert(stringify!($key).to_string(), $crate::FieldValue::Boolean($val));
+        $crate::measure!(@item $tags, $fields, $($($rest)*)?);
+    };
+    (@item $tags:ident, $fields:ident, str $key:ident = $val:expr $(, $($rest:tt)*)?) => {
+        $fields.insert(
+            stringify!($key).to_string(),
+            $crate::FieldValue::String(($val).to_string()),
+        );
+        $crate::measure!(@item $tags, $fields, $($($rest)*)?);
+    };
+    (@item $tags:ident, $fields:ident $(,)?) => {};
+}
+
+#[cfg(test)]
+mod tests {
+    #[test]
+    fn builds_a_measurement_from_tags_and_fields() {
+        let measurement = crate::measure!(
+            "cpu",
+            tag host = "h1",
+            int used = 5,
+            float load = 1.2,
+            bool up = true,
+            str msg = "ok"
+        )
+        .unwrap();
+        let rendered = measurement.to_string();
+        assert!(rendered.starts_with("cpu,host=h1 "));
+        assert!(rendered.contains("used=5i"));
+        assert!(rendered.contains("load=1.2"));
+        assert!(rendered.contains("up=t"));
+        assert!(rendered.contains(r#"msg="ok""#));
+    }
+
+    #[test]
+    fn an_int_field_too_large_for_i64_becomes_an_unsigned_field_not_a_wrapped_negative() {
+        let measurement = crate::measure!("cpu", int big = u64::MAX).unwrap();
+        assert!(measurement
+            .to_string()
+            .contains(&format!("big={}u", u64::MAX)));
+    }
+}
@@ -0,0 +1,163 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use reqwest::{Response, StatusCode};
+
+/// Retry behavior for [`Bucket::write`](crate::Bucket::write) on transient
+/// failures: timeouts, connection errors, HTTP 429 and any 5xx status
+/// (honoring a `Retry-After` header when the server sends one). Other 4xx
+/// failures are always returned immediately.
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    multiplier: f64,
+    max_backoff: Duration,
+    jitter: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(200),
+            multiplier: 2.0,
+            max_backoff: Duration::from_secs(30),
+            jitter: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Maximum number of attempts (including the first) before giving up.
+    /// Defaults to 5.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts;
+        self
+    }
+
+    /// Backoff before the first retry. Defaults to 200ms.
+    pub fn initial_backoff(mut self, initial_backoff: Duration) -> Self {
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Factor the backoff is multiplied by after each retry. Defaults to 2.0.
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// Upper bound on the computed backoff, before jitter. Defaults to 30s.
+    pub fn max_backoff(mut self, max_backoff: Duration) -> Self {
+        self.max_backoff = max_backoff;
+        self
+    }
+
+    /// Whether to randomize each backoff by up to 50% to avoid thundering
+    /// herds of retrying clients. Defaults to `true`.
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    pub(crate) fn max_attempts_value(&self) -> u32 {
+        self.max_attempts
+    }
+
+    pub(crate) fn backoff_for_attempt(&self, attempt: u32) -> Duration {
+        let exponent = attempt.saturating_sub(1) as i32;
+        let factor = self.multiplier.powi(exponent);
+        // Cap the factor before scaling `initial_backoff` by it, not after:
+        // for a large enough `attempt`/`multiplier`, computing the scaled
+        // Duration first and capping second would overflow and panic
+        // inside `Duration::mul_f64` before `.min(max_backoff)` ever runs.
+        let backoff = if self.initial_backoff.is_zero() {
+            Duration::ZERO
+        } else {
+            let max_factor = self.max_backoff.as_secs_f64() / self.initial_backoff.as_secs_f64();
+            self.initial_backoff.mul_f64(factor.min(max_factor))
+        };
+        if self.jitter {
+            backoff.mul_f64(0.5 + jitter_fraction() * 0.5)
+        } else {
+            backoff
+        }
+    }
+}
+
+/// A cheap, dependency-free source of jitter: the sub-microsecond part of
+/// the current time, normalized to `[0.0, 1.0)`.
+fn jitter_fraction() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    (nanos % 1_000) as f64 / 1_000.0
+}
+
+pub(crate) fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+pub(crate) fn is_retryable_error(error: &reqwest::Error) -> bool {
+    error.is_timeout() || error.is_connect()
+}
+
+/// Parses a `Retry-After` header as a number of seconds, per the InfluxDB
+/// and general HTTP convention of sending an integer delay rather than an
+/// HTTP-date for this kind of response.
+pub(crate) fn retry_after(response: &Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_grows_exponentially_without_jitter() {
+        let policy = RetryPolicy::default().jitter(false);
+        assert_eq!(policy.backoff_for_attempt(1), Duration::from_millis(200));
+        assert_eq!(policy.backoff_for_attempt(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff_for_attempt(3), Duration::from_millis(800));
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max_backoff() {
+        let policy = RetryPolicy::default()
+            .max_backoff(Duration::from_secs(1))
+            .jitter(false);
+        assert_eq!(policy.backoff_for_attempt(10), Duration::from_secs(1));
+    }
+
+    #[test]
+    fn backoff_does_not_panic_for_a_large_attempt_count() {
+        // A caller-configured policy with a much larger `max_attempts` than
+        // the default of 5 must still have its backoff bounded by
+        // `max_backoff`, not overflow `Duration::mul_f64` and panic.
+        let policy = RetryPolicy::default()
+            .max_attempts(1_000)
+            .max_backoff(Duration::from_secs(30))
+            .jitter(false);
+        assert_eq!(policy.backoff_for_attempt(1_000), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn jitter_keeps_backoff_within_half_to_full_of_the_unjittered_value() {
+        let policy = RetryPolicy::default();
+        let unjittered = policy.clone().jitter(false).backoff_for_attempt(3);
+        for _ in 0..20 {
+            let jittered = policy.backoff_for_attempt(3);
+            assert!(jittered >= unjittered.mul_f64(0.5));
+            assert!(jittered <= unjittered);
+        }
+    }
+}